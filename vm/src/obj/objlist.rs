@@ -111,6 +111,55 @@ impl PyList {
         start..stop
     }
 
+    /// Normalize a slice's `(start, stop, step)` against this list's length
+    /// the way CPython's `slice.indices(len)` does: clamp `start`/`stop`
+    /// into bounds (with a negative step's defaults running from the end
+    /// down to, and including, index 0) and derive how many elements are
+    /// selected. Returns `(first selected index, signed step, element
+    /// count)`, ready to build a `SliceIndices` from directly — this is what
+    /// a negative step needs instead of remapping `start`/`stop` through a
+    /// forward range first, which is fragile around a literal `-1` bound.
+    fn normalize_slice(
+        &self,
+        slice: &PySliceRef,
+        vm: &VirtualMachine,
+    ) -> PyResult<(usize, isize, usize)> {
+        let len = self.get_len().to_bigint().unwrap();
+        let step = slice.step_index(vm)?.unwrap_or_else(BigInt::one);
+        let negative_step = step.is_negative();
+
+        let default_start = if negative_step { &len - 1 } else { Zero::zero() };
+        let default_stop = if negative_step {
+            (-1).to_bigint().unwrap()
+        } else {
+            len.clone()
+        };
+
+        let start = clamp_slice_bound(slice.start_index(vm)?, &default_start, &len, negative_step);
+        let stop = clamp_slice_bound(slice.stop_index(vm)?, &default_stop, &len, negative_step);
+
+        let slicelen = if negative_step {
+            if stop < start {
+                (&start - &stop - 1) / (-&step) + 1
+            } else {
+                Zero::zero()
+            }
+        } else if start < stop {
+            (&stop - &start - 1) / &step + 1
+        } else {
+            Zero::zero()
+        };
+
+        let step = step
+            .to_isize()
+            .unwrap_or(if negative_step { std::isize::MIN } else { std::isize::MAX });
+        Ok((
+            start.to_usize().unwrap_or(0),
+            step,
+            slicelen.to_usize().unwrap_or(0),
+        ))
+    }
+
     pub(crate) fn get_byte_inner(
         &self,
         vm: &VirtualMachine,
@@ -136,6 +185,259 @@ impl PyList {
     }
 }
 
+/// Clamps one `start`/`stop` slice bound against the list's `len`, the way
+/// CPython's `slice.indices(len)` does: an explicit negative index is
+/// remapped from the end and re-clamped into `0..=len` (or `-1..=len-1` for
+/// a negative step), while an *omitted* bound (`value == None`) is returned
+/// as `default` untouched. That last part matters: `default` can itself be
+/// `-1` (a negative step's implicit stop, meaning "through index 0"), and
+/// that sentinel must not be re-offset by `len` the same way a real
+/// explicit `-1` argument would be, or it collides with `default_start`
+/// (also `len - 1`) and `lst[::-1]`-style slices come out empty.
+fn clamp_slice_bound(
+    value: Option<BigInt>,
+    default: &BigInt,
+    len: &BigInt,
+    negative_step: bool,
+) -> BigInt {
+    let mut v = match value {
+        Some(v) => v,
+        None => return default.clone(),
+    };
+    if v.is_negative() {
+        v += len;
+        if v.is_negative() {
+            v = if negative_step {
+                (-1).to_bigint().unwrap()
+            } else {
+                Zero::zero()
+            };
+        }
+    } else if &v >= len {
+        v = if negative_step { len - 1 } else { len.clone() };
+    }
+    v
+}
+
+/// Sift `elements[pos]` up towards the root of the binary min-heap until its
+/// parent is no longer greater, swapping as it goes. Takes the backing `Vec`
+/// directly (as opposed to borrowing through a `RefCell`) so that a
+/// reentrant `__lt__` that mutates the owning list can't deadlock/panic a
+/// live borrow; see `PyList::with_detached_elements`.
+fn heap_sift_up(vm: &VirtualMachine, elements: &mut [PyObjectRef], mut pos: usize) -> PyResult<()> {
+    while pos > 0 {
+        let parent = (pos - 1) / 2;
+        if !objbool::boolval(vm, vm._lt(elements[pos].clone(), elements[parent].clone())?)? {
+            break;
+        }
+        elements.swap(pos, parent);
+        pos = parent;
+    }
+    Ok(())
+}
+
+/// Sift `elements[pos]` down towards the leaves, swapping with its smaller
+/// child until the heap property is restored. See `heap_sift_up` for why
+/// this takes the backing `Vec` directly.
+fn heap_sift_down(
+    vm: &VirtualMachine,
+    elements: &mut [PyObjectRef],
+    mut pos: usize,
+) -> PyResult<()> {
+    let len = elements.len();
+    loop {
+        let left = 2 * pos + 1;
+        if left >= len {
+            break;
+        }
+        let right = left + 1;
+        let smaller = if right < len
+            && objbool::boolval(vm, vm._lt(elements[right].clone(), elements[left].clone())?)?
+        {
+            right
+        } else {
+            left
+        };
+        if !objbool::boolval(vm, vm._lt(elements[smaller].clone(), elements[pos].clone())?)? {
+            break;
+        }
+        elements.swap(pos, smaller);
+        pos = smaller;
+    }
+    Ok(())
+}
+
+/// Heap primitives backing the `_heapq` module: these treat the list's own
+/// backing `Vec` as a binary min-heap ordered by `__lt__`.
+impl PyList {
+    /// Detaches `self.elements` from its `RefCell` (the same trick `sort`
+    /// uses) and hands the owned `Vec` to `f`, so no borrow is held across
+    /// `f`'s `vm._lt` calls — a custom `__lt__` that re-enters and mutates
+    /// this same list can't trigger a "already borrowed" panic, since it's
+    /// mutating the empty placeholder left behind, not `elements`. If that
+    /// happens, the placeholder isn't actually empty once restored and we
+    /// report it rather than silently discarding the reentrant mutation.
+    fn with_detached_elements<F, R>(&self, vm: &VirtualMachine, f: F) -> PyResult<R>
+    where
+        F: FnOnce(&mut Vec<PyObjectRef>) -> PyResult<R>,
+    {
+        let mut elements = self.elements.replace(Vec::new());
+        let result = f(&mut elements);
+        let leftover = self.elements.replace(elements);
+        if !leftover.is_empty() {
+            return Err(vm.new_value_error("list modified during heap operation".to_owned()));
+        }
+        result
+    }
+
+    /// `_heapq.heappush`: append then sift the new node up towards the root.
+    pub(crate) fn heap_push(&self, item: PyObjectRef, vm: &VirtualMachine) -> PyResult<()> {
+        self.with_detached_elements(vm, |elements| {
+            elements.push(item);
+            let pos = elements.len() - 1;
+            heap_sift_up(vm, elements, pos)
+        })
+    }
+
+    /// `_heapq.heappop`: move the last element to the root and sift it down,
+    /// returning the old root.
+    pub(crate) fn heap_pop(&self, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+        self.with_detached_elements(vm, |elements| {
+            let last = elements
+                .pop()
+                .ok_or_else(|| vm.new_index_error("index out of range".to_owned()))?;
+            if elements.is_empty() {
+                return Ok(last);
+            }
+            let root = std::mem::replace(&mut elements[0], last);
+            heap_sift_down(vm, elements, 0)?;
+            Ok(root)
+        })
+    }
+
+    /// `_heapq.heapify`: sift down from the last parent node to the root.
+    pub(crate) fn heapify(&self, vm: &VirtualMachine) -> PyResult<()> {
+        self.with_detached_elements(vm, |elements| {
+            let len = elements.len();
+            for pos in (0..len / 2).rev() {
+                heap_sift_down(vm, elements, pos)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// `_heapq.heappushpop`: push `item`, then pop and return the smallest.
+    /// When the heap is empty or `item` is already the smallest, this is
+    /// equivalent to (and faster than) a push immediately followed by a pop.
+    pub(crate) fn heap_push_pop(&self, item: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        self.with_detached_elements(vm, |elements| {
+            if elements.is_empty() {
+                return Ok(item);
+            }
+            let root_is_smaller =
+                objbool::boolval(vm, vm._lt(elements[0].clone(), item.clone())?)?;
+            if !root_is_smaller {
+                return Ok(item);
+            }
+            let root = std::mem::replace(&mut elements[0], item);
+            heap_sift_down(vm, elements, 0)?;
+            Ok(root)
+        })
+    }
+
+    /// `_heapq.heapreplace`: pop and return the smallest, then push `item`.
+    pub(crate) fn heap_replace(&self, item: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        self.with_detached_elements(vm, |elements| {
+            if elements.is_empty() {
+                return Err(vm.new_index_error("index out of range".to_owned()));
+            }
+            let root = std::mem::replace(&mut elements[0], item);
+            heap_sift_down(vm, elements, 0)?;
+            Ok(root)
+        })
+    }
+}
+
+/// Concrete positions selected by an (extended) slice, in slice order.
+///
+/// Given the forward-clamped `start..stop` bounds of a slice and its
+/// absolute step, this normalizes CPython's `slice.indices(len)` logic
+/// into a single reusable iterator: `reverse` picks whether the slice
+/// actually walks the range back-to-front (a negative Python step), and
+/// `ExactSizeIterator::len()` gives the slice length directly instead of
+/// every caller re-deriving it with `(end - start - 1) / step + 1`.
+struct SliceIndices {
+    start: usize,
+    step: isize,
+    front: usize,
+    back: usize,
+}
+
+impl SliceIndices {
+    /// Build directly from already-normalized parameters (the first selected
+    /// index, the signed step, and how many elements are selected), e.g. as
+    /// produced by `PyList::normalize_slice`.
+    fn new(start: usize, step: isize, len: usize) -> Self {
+        SliceIndices {
+            start,
+            step,
+            front: 0,
+            back: len,
+        }
+    }
+
+    /// Build from a forward-clamped `start..stop` bound plus an absolute
+    /// step and direction, deriving the slice length from the range so
+    /// callers don't have to compute it themselves.
+    fn from_range(range: Range<usize>, step: usize, reverse: bool) -> Self {
+        let len = if range.end > range.start {
+            (range.end - range.start - 1) / step + 1
+        } else {
+            0
+        };
+        let (start, step) = if reverse {
+            (range.end.wrapping_sub(1), -(step as isize))
+        } else {
+            (range.start, step as isize)
+        };
+        SliceIndices::new(start, step, len)
+    }
+
+    fn index_at(&self, i: usize) -> usize {
+        (self.start as isize + i as isize * self.step) as usize
+    }
+}
+
+impl Iterator for SliceIndices {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.front >= self.back {
+            return None;
+        }
+        let idx = self.index_at(self.front);
+        self.front += 1;
+        Some(idx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.back - self.front;
+        (n, Some(n))
+    }
+}
+
+impl ExactSizeIterator for SliceIndices {}
+
+impl DoubleEndedIterator for SliceIndices {
+    fn next_back(&mut self) -> Option<usize> {
+        if self.front >= self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.index_at(self.back))
+    }
+}
+
 #[derive(FromArgs)]
 struct SortOptions {
     #[pyarg(keyword_only, default = "None")]
@@ -144,19 +446,41 @@ struct SortOptions {
     reverse: bool,
 }
 
+/// Mirrors CPython's `list_resize`: grow the backing allocation with a mild
+/// proportional overallocation instead of relying on `Vec`'s default
+/// doubling, and shrink it back down once usage drops under half of
+/// capacity, so `sys.getsizeof` on a list reports allocation behavior that
+/// matches CPython rather than whatever `Vec` happens to do.
+fn ensure_capacity(elements: &mut Vec<PyObjectRef>, newsize: usize) {
+    let capacity = elements.capacity();
+    if newsize > capacity {
+        let new_allocated = newsize + (newsize >> 3) + if newsize < 9 { 3 } else { 6 };
+        elements.reserve_exact(new_allocated - elements.len());
+    } else if capacity > 0 && newsize < capacity / 2 {
+        let new_allocated = newsize + (newsize >> 3) + if newsize < 9 { 3 } else { 6 };
+        let mut shrunk = Vec::with_capacity(new_allocated);
+        shrunk.append(elements);
+        *elements = shrunk;
+    }
+}
+
 pub type PyListRef = PyRef<PyList>;
 
 #[pyimpl(flags(BASETYPE))]
 impl PyList {
     #[pymethod]
     pub(crate) fn append(&self, x: PyObjectRef) {
-        self.elements.borrow_mut().push(x);
+        let mut elements = self.elements.borrow_mut();
+        ensure_capacity(&mut elements, elements.len() + 1);
+        elements.push(x);
     }
 
     #[pymethod]
     fn extend(&self, x: PyObjectRef, vm: &VirtualMachine) -> PyResult<()> {
         let mut new_elements = vm.extract_elements(&x)?;
-        self.elements.borrow_mut().append(&mut new_elements);
+        let mut elements = self.elements.borrow_mut();
+        ensure_capacity(&mut elements, elements.len() + new_elements.len());
+        elements.append(&mut new_elements);
         Ok(())
     }
 
@@ -172,6 +496,7 @@ impl PyList {
         };
         // Bound it by [0, vec.len()]
         let position = unbounded_position.max(0).min(vec_len).to_usize().unwrap();
+        ensure_capacity(&mut vec, vec.len() + 1);
         vec.insert(position, element.clone());
     }
 
@@ -298,13 +623,17 @@ impl PyList {
                     Some(1) => self._set_slice(range, sec, vm),
                     Some(num) => {
                         // assign to extended slice
-                        self._set_stepped_slice(range, num as usize, sec, vm)
+                        self._set_stepped_slice(SliceIndices::from_range(range, num as usize, false), sec, vm)
                     }
                     None => {
                         // not sure how this is reached, step too big for i32?
                         // then step is bigger than the than len of the list, no question
                         #[allow(clippy::range_plus_one)]
-                        self._set_stepped_slice(range.start..(range.start + 1), 1, sec, vm)
+                        self._set_stepped_slice(
+                            SliceIndices::from_range(range.start..(range.start + 1), 1, false),
+                            sec,
+                            vm,
+                        )
                     }
                 }
             } else {
@@ -312,31 +641,10 @@ impl PyList {
                 self._set_slice(range.start..range.start, sec, vm)
             }
         } else {
-            // calculate the range for the reverse slice, first the bounds needs to be made
-            // exclusive around stop, the lower number
-            let start = &slice.start_index(vm)?.as_ref().map(|x| {
-                if *x == (-1).to_bigint().unwrap() {
-                    self.get_len() + BigInt::one() //.to_bigint().unwrap()
-                } else {
-                    x + 1
-                }
-            });
-            let stop = &slice.stop_index(vm)?.as_ref().map(|x| {
-                if *x == (-1).to_bigint().unwrap() {
-                    self.get_len().to_bigint().unwrap()
-                } else {
-                    x + 1
-                }
-            });
-            let range = self.get_slice_range(&stop, &start);
-            match (-step).to_i32() {
-                Some(num) => self._set_stepped_slice_reverse(range, num as usize, sec, vm),
-                None => {
-                    // not sure how this is reached, step too big for i32?
-                    // then step is bigger than the than len of the list no question
-                    self._set_stepped_slice_reverse(range.end - 1..range.end, 1, sec, vm)
-                }
-            }
+            // CPython's slice.indices(len), computed directly instead of
+            // remapping start/stop through a forward range first
+            let (start, step, slicelen) = self.normalize_slice(&slice, vm)?;
+            self._set_stepped_slice(SliceIndices::new(start, step, slicelen), sec, vm)
         }
     }
 
@@ -347,65 +655,24 @@ impl PyList {
         let items = items?;
 
         // replace the range of elements with the full sequence
-        self.elements.borrow_mut().splice(range, items);
+        let mut elements = self.elements.borrow_mut();
+        let newsize = elements.len() - (range.end - range.start) + items.len();
+        // grow before the splice, but shrink only after it: shrinking first
+        // would have to hold every current element in the smaller buffer
+        let grows = newsize > elements.len();
+        if grows {
+            ensure_capacity(&mut elements, newsize);
+        }
+        elements.splice(range, items);
+        if !grows {
+            ensure_capacity(&mut elements, newsize);
+        }
 
         Ok(vm.get_none())
     }
 
-    fn _set_stepped_slice(
-        &self,
-        range: Range<usize>,
-        step: usize,
-        sec: PyIterable,
-        vm: &VirtualMachine,
-    ) -> PyResult {
-        let slicelen = if range.end > range.start {
-            ((range.end - range.start - 1) / step) + 1
-        } else {
-            0
-        };
-        // consume the iter, we  need it's size
-        // and if it's going to fail we want that to happen *before* we start modifing
-        let items: Result<Vec<PyObjectRef>, _> = sec.iter(vm)?.collect();
-        let items = items?;
-
-        let n = items.len();
-
-        if range.start < range.end {
-            if n == slicelen {
-                let indexes = range.step_by(step);
-                self._replace_indexes(indexes, &items);
-                Ok(vm.get_none())
-            } else {
-                Err(vm.new_value_error(format!(
-                    "attempt to assign sequence of size {} to extended slice of size {}",
-                    n, slicelen
-                )))
-            }
-        } else if n == 0 {
-            // slice is empty but so is sequence
-            Ok(vm.get_none())
-        } else {
-            // empty slice but this is an error because stepped slice
-            Err(vm.new_value_error(format!(
-                "attempt to assign sequence of size {} to extended slice of size 0",
-                n
-            )))
-        }
-    }
-
-    fn _set_stepped_slice_reverse(
-        &self,
-        range: Range<usize>,
-        step: usize,
-        sec: PyIterable,
-        vm: &VirtualMachine,
-    ) -> PyResult {
-        let slicelen = if range.end > range.start {
-            ((range.end - range.start - 1) / step) + 1
-        } else {
-            0
-        };
+    fn _set_stepped_slice(&self, indexes: SliceIndices, sec: PyIterable, vm: &VirtualMachine) -> PyResult {
+        let slicelen = indexes.len();
 
         // consume the iter, we  need it's size
         // and if it's going to fail we want that to happen *before* we start modifing
@@ -414,9 +681,8 @@ impl PyList {
 
         let n = items.len();
 
-        if range.start < range.end {
+        if slicelen > 0 {
             if n == slicelen {
-                let indexes = range.rev().step_by(step);
                 self._replace_indexes(indexes, &items);
                 Ok(vm.get_none())
             } else {
@@ -648,7 +914,7 @@ impl PyList {
                         Ok(())
                     }
                     Some(num) => {
-                        self._del_stepped_slice(range, num as usize);
+                        self._del_stepped_slice(SliceIndices::from_range(range, num as usize, false));
                         Ok(())
                     }
                     None => {
@@ -661,42 +927,11 @@ impl PyList {
                 Ok(())
             }
         } else {
-            // calculate the range for the reverse slice, first the bounds needs to be made
-            // exclusive around stop, the lower number
-            let start = start.as_ref().map(|x| {
-                if *x == (-1).to_bigint().unwrap() {
-                    self.get_len() + BigInt::one() //.to_bigint().unwrap()
-                } else {
-                    x + 1
-                }
-            });
-            let stop = stop.as_ref().map(|x| {
-                if *x == (-1).to_bigint().unwrap() {
-                    self.get_len().to_bigint().unwrap()
-                } else {
-                    x + 1
-                }
-            });
-            let range = self.get_slice_range(&stop, &start);
-            if range.start < range.end {
-                match (-step).to_i32() {
-                    Some(1) => {
-                        self._del_slice(range);
-                        Ok(())
-                    }
-                    Some(num) => {
-                        self._del_stepped_slice_reverse(range, num as usize);
-                        Ok(())
-                    }
-                    None => {
-                        self._del_slice(range.end - 1..range.end);
-                        Ok(())
-                    }
-                }
-            } else {
-                // no del to do
-                Ok(())
-            }
+            // CPython's slice.indices(len), computed directly instead of
+            // remapping start/stop through a forward range first
+            let (start, step, slicelen) = self.normalize_slice(&slice, vm)?;
+            self._del_stepped_slice(SliceIndices::new(start, step, slicelen));
+            Ok(())
         }
     }
 
@@ -704,46 +939,17 @@ impl PyList {
         self.elements.borrow_mut().drain(range);
     }
 
-    fn _del_stepped_slice(&self, range: Range<usize>, step: usize) {
-        // no easy way to delete stepped indexes so here is what we'll do
-        let mut deleted = 0;
-        let mut elements = self.elements.borrow_mut();
-        let mut indexes = range.clone().step_by(step).peekable();
-
-        for i in range.clone() {
-            // is this an index to delete?
-            if indexes.peek() == Some(&i) {
-                // record and move on
-                indexes.next();
-                deleted += 1;
-            } else {
-                // swap towards front
-                elements.swap(i - deleted, i);
-            }
-        }
-        // then drain (the values to delete should now be contiguous at the end of the range)
-        elements.drain((range.end - deleted)..range.end);
-    }
-
-    fn _del_stepped_slice_reverse(&self, range: Range<usize>, step: usize) {
-        // no easy way to delete stepped indexes so here is what we'll do
-        let mut deleted = 0;
-        let mut elements = self.elements.borrow_mut();
-        let mut indexes = range.clone().rev().step_by(step).peekable();
-
-        for i in range.clone().rev() {
-            // is this an index to delete?
-            if indexes.peek() == Some(&i) {
-                // record and move on
-                indexes.next();
-                deleted += 1;
-            } else {
-                // swap towards back
-                elements.swap(i + deleted, i);
-            }
-        }
-        // then drain (the values to delete should now be contiguous at teh start of the range)
-        elements.drain(range.start..(range.start + deleted));
+    fn _del_stepped_slice(&self, indices: SliceIndices) {
+        // SliceIndices already knows exactly which positions are selected,
+        // forwards or backwards, so deletion collapses into a single
+        // retain pass instead of two mirror-image swap-and-drain dances.
+        let to_delete: std::collections::HashSet<usize> = indices.collect();
+        let mut i = 0;
+        self.elements.borrow_mut().retain(|_| {
+            let keep = !to_delete.contains(&i);
+            i += 1;
+            keep
+        });
     }
 
     #[pymethod]
@@ -778,45 +984,130 @@ impl PyList {
     }
 }
 
-fn quicksort(
+/// Stable merge of the two already-sorted halves `keys[..mid]`/`values[..mid]`
+/// and `keys[mid..]`/`values[mid..]` into `scratch`, then copied back.
+/// Order is decided purely from `keys` (`values` just rides along), and the
+/// left half is preferred on a tie so equal keys keep their relative order.
+/// `vm._lt` can raise, so the merge is fallible and bails out as soon as a
+/// comparison does.
+fn merge(
     vm: &VirtualMachine,
     keys: &mut [PyObjectRef],
     values: &mut [PyObjectRef],
+    mid: usize,
+    scratch_keys: &mut Vec<PyObjectRef>,
+    scratch_values: &mut Vec<PyObjectRef>,
 ) -> PyResult<()> {
-    let len = values.len();
-    if len >= 2 {
-        let pivot = partition(vm, keys, values)?;
-        quicksort(vm, &mut keys[0..pivot], &mut values[0..pivot])?;
-        quicksort(vm, &mut keys[pivot + 1..len], &mut values[pivot + 1..len])?;
+    scratch_keys.clear();
+    scratch_values.clear();
+    scratch_keys.reserve(keys.len());
+    scratch_values.reserve(values.len());
+
+    let (mut i, mut j) = (0, mid);
+    while i < mid && j < keys.len() {
+        // only take from the right if it's *strictly* less than the left,
+        // otherwise prefer the left: this is what keeps the sort stable
+        let right_is_less = objbool::boolval(vm, vm._lt(keys[j].clone(), keys[i].clone())?)?;
+        if right_is_less {
+            scratch_keys.push(keys[j].clone());
+            scratch_values.push(values[j].clone());
+            j += 1;
+        } else {
+            scratch_keys.push(keys[i].clone());
+            scratch_values.push(values[i].clone());
+            i += 1;
+        }
     }
+    scratch_keys.extend_from_slice(&keys[i..mid]);
+    scratch_values.extend_from_slice(&values[i..mid]);
+    scratch_keys.extend_from_slice(&keys[j..]);
+    scratch_values.extend_from_slice(&values[j..]);
+
+    keys.clone_from_slice(scratch_keys);
+    values.clone_from_slice(scratch_values);
     Ok(())
 }
 
-fn partition(
+/// Size of the runs `merge_sort` builds with `binary_insertion_sort` before
+/// merging them. CPython's timsort uses the same idea: insertion sort is
+/// cheap on small slices and gives the merge passes a head start, especially
+/// on data that's already mostly ordered.
+const MIN_RUN: usize = 32;
+
+/// Sort `keys[lo..i]` is already ordered; insert `keys[i]` (and its rider in
+/// `values`) at its correct position via a binary search over the sorted
+/// prefix rather than a linear scan.
+fn binary_insertion_sort(
     vm: &VirtualMachine,
     keys: &mut [PyObjectRef],
     values: &mut [PyObjectRef],
-) -> PyResult<usize> {
-    let len = values.len();
-    let pivot = len / 2;
-
-    values.swap(pivot, len - 1);
-    keys.swap(pivot, len - 1);
+) -> PyResult<()> {
+    for i in 1..keys.len() {
+        let (mut lo, mut hi) = (0, i);
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            let key_lt_mid = objbool::boolval(vm, vm._lt(keys[i].clone(), keys[mid].clone())?)?;
+            if key_lt_mid {
+                hi = mid;
+            } else {
+                lo = mid + 1;
+            }
+        }
+        if lo < i {
+            keys[lo..=i].rotate_right(1);
+            values[lo..=i].rotate_right(1);
+        }
+    }
+    Ok(())
+}
 
-    let mut store_idx = 0;
-    for i in 0..len - 1 {
-        let result = vm._lt(keys[i].clone(), keys[len - 1].clone())?;
-        let boolval = objbool::boolval(vm, result)?;
-        if boolval {
-            values.swap(i, store_idx);
-            keys.swap(i, store_idx);
-            store_idx += 1;
+/// Bottom-up merge sort: build `MIN_RUN`-sized sorted runs with binary
+/// insertion, then iteratively merge adjacent runs with doubling width until
+/// a single run spans the whole slice, staying stable and fallible
+/// throughout. Runs already present in the input are not detected: the run
+/// boundaries are fixed and every merge pass runs, so already-ordered input
+/// takes the same number of passes as shuffled input.
+fn merge_sort(
+    vm: &VirtualMachine,
+    keys: &mut [PyObjectRef],
+    values: &mut [PyObjectRef],
+    scratch_keys: &mut Vec<PyObjectRef>,
+    scratch_values: &mut Vec<PyObjectRef>,
+) -> PyResult<()> {
+    let len = keys.len();
+    if len < 2 {
+        return Ok(());
+    }
+
+    let mut start = 0;
+    while start < len {
+        let end = (start + MIN_RUN).min(len);
+        binary_insertion_sort(vm, &mut keys[start..end], &mut values[start..end])?;
+        start = end;
+    }
+
+    let mut width = MIN_RUN;
+    while width < len {
+        let mut lo = 0;
+        while lo < len {
+            let mid = (lo + width).min(len);
+            let hi = (lo + 2 * width).min(len);
+            if mid < hi {
+                merge(
+                    vm,
+                    &mut keys[lo..hi],
+                    &mut values[lo..hi],
+                    mid - lo,
+                    scratch_keys,
+                    scratch_values,
+                )?;
+            }
+            lo += 2 * width;
         }
+        width *= 2;
     }
 
-    values.swap(store_idx, len - 1);
-    keys.swap(store_idx, len - 1);
-    Ok(store_idx)
+    Ok(())
 }
 
 fn do_sort(
@@ -825,8 +1116,9 @@ fn do_sort(
     key_func: Option<PyObjectRef>,
     reverse: bool,
 ) -> PyResult<()> {
-    // build a list of keys. If no keyfunc is provided, it's a copy of the list.
-    let mut keys: Vec<PyObjectRef> = vec![];
+    // decorate: call key(x) exactly once per element, never again during
+    // comparisons, then sort the (key, original) pairs by key alone
+    let mut keys: Vec<PyObjectRef> = Vec::with_capacity(values.len());
     for x in values.iter() {
         keys.push(match &key_func {
             None => x.clone(),
@@ -834,7 +1126,16 @@ fn do_sort(
         });
     }
 
-    quicksort(vm, &mut keys, values)?;
+    // reverse, sort ascending, reverse again: reversing the final vector
+    // directly would put equal elements in the wrong relative order
+    if reverse {
+        keys.reverse();
+        values.reverse();
+    }
+
+    let mut scratch_keys = Vec::with_capacity(keys.len());
+    let mut scratch_values = Vec::with_capacity(values.len());
+    merge_sort(vm, &mut keys, values, &mut scratch_keys, &mut scratch_values)?;
 
     if reverse {
         values.reverse();
@@ -930,3 +1231,154 @@ pub fn init(context: &PyContext) {
     PyListIterator::extend_class(context, &context.types.listiterator_type);
     PyListReverseIterator::extend_class(context, &context.types.listreverseiterator_type);
 }
+
+// The `_heapq` module itself: thin `#[pyfunction]` wrappers around the heap
+// primitives above, matching the free-function shape of CPython's C
+// accelerator (`heapq.heappush(heap, item)` rather than a list method).
+// Registering `make_module` in the interpreter's builtin module table is the
+// one remaining step, and lives in the stdlib module registry outside this
+// file.
+#[pymodule]
+mod _heapq {
+    use super::PyListRef;
+    use crate::pyobject::{PyObjectRef, PyResult};
+    use crate::vm::VirtualMachine;
+
+    #[pyfunction]
+    fn heappush(heap: PyListRef, item: PyObjectRef, vm: &VirtualMachine) -> PyResult<()> {
+        heap.heap_push(item, vm)
+    }
+
+    #[pyfunction]
+    fn heappop(heap: PyListRef, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+        heap.heap_pop(vm)
+    }
+
+    #[pyfunction]
+    fn heapify(heap: PyListRef, vm: &VirtualMachine) -> PyResult<()> {
+        heap.heapify(vm)
+    }
+
+    #[pyfunction]
+    fn heappushpop(heap: PyListRef, item: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        heap.heap_push_pop(item, vm)
+    }
+
+    #[pyfunction]
+    fn heapreplace(heap: PyListRef, item: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+        heap.heap_replace(item, vm)
+    }
+}
+pub(crate) use _heapq::make_module;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // `clamp_slice_bound` is the VM-independent arithmetic core of stepped
+    // slicing; exercising it directly (rather than through `normalize_slice`,
+    // which also needs a live `VirtualMachine` to pull `start`/`stop`/`step`
+    // off a `PySliceRef`) is what lets these cases run without one.
+    //
+    // NOTE: heap reentrancy (the `with_detached_elements` fix for a custom
+    // `__lt__` that mutates the list mid-comparison) is not covered here.
+    // That needs a constructible `VirtualMachine` plus a Python-level class
+    // with a custom `__lt__` to actually re-enter `heap_push`/`heap_pop`,
+    // and this tree is a standalone `objlist.rs`/`objstr.rs` fragment with
+    // no `crate::vm`, class registry, or interpreter to build one in a unit
+    // test; `with_detached_elements`'s leftover-check was instead verified
+    // by manual trace (see its doc comment and c0af4d7's commit message).
+
+    fn bi(n: i64) -> BigInt {
+        n.to_bigint().unwrap()
+    }
+
+    #[test]
+    fn omitted_stop_sentinel_is_not_reoffset_by_len() {
+        // 5-element list, `lst[::-1]`: step < 0, stop omitted. The sentinel
+        // default_stop (-1) must survive clamping unchanged, not collide
+        // with default_start (len - 1 = 4).
+        let len = bi(5);
+        let default_stop = bi(-1);
+        assert_eq!(clamp_slice_bound(None, &default_stop, &len, true), bi(-1));
+    }
+
+    #[test]
+    fn explicit_negative_one_stop_is_remapped_to_last_index() {
+        // An *explicit* stop=-1 under a negative step means "the last
+        // element", which is a real index (len - 1), unlike the omitted
+        // sentinel above -- the two must not be conflated.
+        let len = bi(5);
+        let default_stop = bi(-1);
+        assert_eq!(
+            clamp_slice_bound(Some(bi(-1)), &default_stop, &len, true),
+            bi(4)
+        );
+    }
+
+    #[test]
+    fn full_reverse_slice_selects_every_element() {
+        // lst[::-1] on a 5-element list: start defaults to len - 1 = 4,
+        // stop defaults to the omitted sentinel (-1, left unclamped), so
+        // slicelen should come out to the full length, not 0.
+        let len = bi(5);
+        let default_start = &len - 1;
+        let default_stop = bi(-1);
+        let start = clamp_slice_bound(None, &default_start, &len, true);
+        let stop = clamp_slice_bound(None, &default_stop, &len, true);
+        assert_eq!(start, bi(4));
+        assert_eq!(stop, bi(-1));
+        let slicelen = if stop < start {
+            (&start - &stop - 1) / 1 + 1
+        } else {
+            Zero::zero()
+        };
+        assert_eq!(slicelen, bi(5));
+    }
+
+    #[test]
+    fn explicit_start_with_omitted_stop_under_negative_step() {
+        // lst[3::-1] on a 5-element list: start=3 explicit, stop omitted ->
+        // sentinel -1, selecting indices 3, 2, 1, 0 (4 elements).
+        let len = bi(5);
+        let default_stop = bi(-1);
+        let start = clamp_slice_bound(Some(bi(3)), &bi(4), &len, true);
+        let stop = clamp_slice_bound(None, &default_stop, &len, true);
+        assert_eq!(start, bi(3));
+        assert_eq!(stop, bi(-1));
+        let slicelen = if stop < start {
+            (&start - &stop - 1) / 1 + 1
+        } else {
+            Zero::zero()
+        };
+        assert_eq!(slicelen, bi(4));
+    }
+
+    #[test]
+    fn positive_step_defaults_are_unaffected() {
+        // Sanity check that the omitted-bound short-circuit doesn't disturb
+        // the (already-correct) forward-step case: lst[:] on a 5-element
+        // list should still select the whole list.
+        let len = bi(5);
+        let start = clamp_slice_bound(None, &Zero::zero(), &len, false);
+        let stop = clamp_slice_bound(None, &len, &len, false);
+        assert_eq!(start, bi(0));
+        assert_eq!(stop, bi(5));
+    }
+
+    #[test]
+    fn out_of_range_explicit_bounds_still_clamp() {
+        // Explicit bounds beyond the list's length clamp to the nearest
+        // valid edge, same as before this fix -- only the *omitted* case
+        // changed.
+        let len = bi(5);
+        assert_eq!(
+            clamp_slice_bound(Some(bi(100)), &Zero::zero(), &len, false),
+            bi(5)
+        );
+        assert_eq!(
+            clamp_slice_bound(Some(bi(-100)), &len, &len, false),
+            bi(0)
+        );
+    }
+}